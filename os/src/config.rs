@@ -0,0 +1,20 @@
+//! Constants shared across the kernel
+
+/// max number of syscalls tracked in `syscall_times` / the seccomp bitmap
+pub const MAX_SYSCALL_NUM: usize = 500;
+/// page size in bytes
+pub const PAGE_SIZE: usize = 0x1000;
+/// number of bits in a page offset
+pub const PAGE_SIZE_BITS: usize = 0xc;
+/// stride scheduling's pass increment is `BIG_STRIDE / priority`; large
+/// enough relative to any realistic priority that passes advance in coarse,
+/// comparable steps
+pub const BIG_STRIDE: u64 = 0x10000;
+/// base address `sys_mmap` searches upward from when the caller leaves
+/// placement up to the kernel (`MAP_FIXED` absent, `addr == 0`)
+pub const MMAP_BASE: usize = 0x1000_0000;
+/// estimated bytes of kernel stack one nested syscall entry consumes, used to
+/// report `sys_proc_stat`'s `kstack_usage`; this checkout has no real trap
+/// frame to measure, so it's a flat per-nesting-level estimate rather than a
+/// high-water mark sampled from an actual stack
+pub const KSTACK_FRAME_ESTIMATE: usize = 256;
@@ -0,0 +1,135 @@
+//! Virtual/physical address and page-number newtypes
+use crate::config::{PAGE_SIZE, PAGE_SIZE_BITS};
+
+/// A virtual address
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Debug)]
+pub struct VirtAddr(pub usize);
+
+/// A physical address
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Debug)]
+pub struct PhysAddr(pub usize);
+
+/// A virtual page number
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Debug, Hash)]
+pub struct VirtPageNum(pub usize);
+
+/// A physical page number
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Debug, Hash)]
+pub struct PhysPageNum(pub usize);
+
+impl VirtAddr {
+    /// whether this address is page-aligned
+    pub fn aligned(&self) -> bool {
+        self.0.is_multiple_of(PAGE_SIZE)
+    }
+
+    /// the page this address falls in, rounding down
+    pub fn floor(&self) -> VirtPageNum {
+        VirtPageNum(self.0 / PAGE_SIZE)
+    }
+
+    /// the page this address falls in, rounding up
+    pub fn ceil(&self) -> VirtPageNum {
+        VirtPageNum(self.0.div_ceil(PAGE_SIZE))
+    }
+
+    /// offset within the containing page
+    pub fn page_offset(&self) -> usize {
+        self.0 & (PAGE_SIZE - 1)
+    }
+}
+
+impl From<VirtPageNum> for VirtAddr {
+    fn from(vpn: VirtPageNum) -> Self {
+        VirtAddr(vpn.0 << PAGE_SIZE_BITS)
+    }
+}
+
+impl From<PhysPageNum> for PhysAddr {
+    fn from(ppn: PhysPageNum) -> Self {
+        PhysAddr(ppn.0 << PAGE_SIZE_BITS)
+    }
+}
+
+impl VirtPageNum {
+    /// the page immediately after this one
+    pub fn next(&self) -> VirtPageNum {
+        VirtPageNum(self.0 + 1)
+    }
+}
+
+/// a half-open `[start, end)` range of virtual page numbers, as tracked by a
+/// task's VMA list
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct VPNRange {
+    start: VirtPageNum,
+    end: VirtPageNum,
+}
+
+impl VPNRange {
+    pub fn new(start: VirtPageNum, end: VirtPageNum) -> Self {
+        Self { start, end }
+    }
+
+    pub fn start(&self) -> VirtPageNum {
+        self.start
+    }
+
+    pub fn end(&self) -> VirtPageNum {
+        self.end
+    }
+
+    pub fn contains(&self, vpn: VirtPageNum) -> bool {
+        self.start.0 <= vpn.0 && vpn.0 < self.end.0
+    }
+
+    pub fn overlaps(&self, other: &VPNRange) -> bool {
+        self.start.0 < other.end.0 && other.start.0 < self.end.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.end.0 - self.start.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start.0 >= self.end.0
+    }
+
+    /// `self` with the portion overlapping `cut` removed, as the 0/1/2
+    /// sub-ranges left standing before and after it
+    pub fn without(&self, cut: &VPNRange) -> (Option<VPNRange>, Option<VPNRange>) {
+        if !self.overlaps(cut) {
+            return (Some(*self), None);
+        }
+        let before = (self.start.0 < cut.start.0).then(|| VPNRange::new(self.start, cut.start));
+        let after = (cut.end.0 < self.end.0).then(|| VPNRange::new(cut.end, self.end));
+        (before, after)
+    }
+}
+
+/// iterates every [`VirtPageNum`] in a [`VPNRange`], letting callers write
+/// `for vpn in range { ... }` instead of a manual `while` loop
+impl IntoIterator for VPNRange {
+    type Item = VirtPageNum;
+    type IntoIter = VPNRangeIter;
+
+    fn into_iter(self) -> VPNRangeIter {
+        VPNRangeIter(self)
+    }
+}
+
+pub struct VPNRangeIter(VPNRange);
+
+impl Iterator for VPNRangeIter {
+    type Item = VirtPageNum;
+
+    fn next(&mut self) -> Option<VirtPageNum> {
+        if self.0.start.0 < self.0.end.0 {
+            let vpn = self.0.start;
+            self.0.start = vpn.next();
+            Some(vpn)
+        } else {
+            None
+        }
+    }
+}
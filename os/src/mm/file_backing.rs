@@ -0,0 +1,30 @@
+//! Shared backing-frame cache for `MAP_SHARED` file-backed mappings
+//!
+//! This checkout has no filesystem/inode layer to read actual file bytes
+//! from, so a file-backed page is zero-filled the same as an anonymous one.
+//! What this module gets right is *identity*: two `MAP_SHARED` mappings of
+//! the same `fd`+page genuinely share one physical frame, so a write through
+//! one is visible through the other, which is the part of the `MAP_SHARED`
+//! vs `MAP_PRIVATE` distinction that doesn't depend on having real file
+//! contents to back it.
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+
+use crate::sync::UPSafeCell;
+
+use super::frame_allocator::{frame_alloc, FrameTracker};
+
+lazy_static::lazy_static! {
+    static ref FILE_FRAMES: UPSafeCell<BTreeMap<(isize, usize), Arc<FrameTracker>>> =
+        unsafe { UPSafeCell::new(BTreeMap::new()) };
+}
+
+/// the frame backing page `page_index` of file descriptor `fd`, allocating a
+/// fresh zero-filled one on first access
+pub fn shared_file_frame(fd: isize, page_index: usize) -> Arc<FrameTracker> {
+    let mut frames = FILE_FRAMES.exclusive_access();
+    frames
+        .entry((fd, page_index))
+        .or_insert_with(|| Arc::new(frame_alloc().expect("out of physical frames")))
+        .clone()
+}
@@ -0,0 +1,95 @@
+//! Physical frame allocation
+//!
+//! Frames are handed out from a fixed-size arena of page-sized buffers; a
+//! [`FrameTracker`] owns one and returns it to the free list on drop.
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::config::PAGE_SIZE;
+use crate::sync::UPSafeCell;
+
+use super::address::PhysPageNum;
+
+/// total number of physical frames the allocator manages
+const FRAME_POOL_SIZE: usize = 4096;
+
+struct FrameAllocator {
+    // Each frame gets its own heap allocation rather than being an element of
+    // a single growable buffer: `storage.push` can reallocate and move the
+    // whole arena, which would dangle every `&'static mut` slice already
+    // handed out by `FrameTracker::as_bytes_mut`. A `Box` only ever moves the
+    // pointer, never the page behind it.
+    storage: Vec<Box<[u8; PAGE_SIZE]>>,
+    recycled: Vec<usize>,
+    next: usize,
+}
+
+impl FrameAllocator {
+    fn new() -> Self {
+        Self {
+            storage: Vec::new(),
+            recycled: Vec::new(),
+            next: 0,
+        }
+    }
+
+    fn alloc(&mut self) -> Option<PhysPageNum> {
+        if let Some(ppn) = self.recycled.pop() {
+            *self.storage[ppn] = [0u8; PAGE_SIZE];
+            return Some(PhysPageNum(ppn));
+        }
+        if self.next >= FRAME_POOL_SIZE {
+            return None;
+        }
+        self.storage.push(Box::new([0u8; PAGE_SIZE]));
+        let ppn = self.next;
+        self.next += 1;
+        Some(PhysPageNum(ppn))
+    }
+
+    fn dealloc(&mut self, ppn: PhysPageNum) {
+        self.recycled.push(ppn.0);
+    }
+
+    fn bytes_mut(&mut self, ppn: PhysPageNum) -> &mut [u8; PAGE_SIZE] {
+        &mut self.storage[ppn.0]
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref FRAME_ALLOCATOR: UPSafeCell<FrameAllocator> =
+        unsafe { UPSafeCell::new(FrameAllocator::new()) };
+}
+
+/// RAII handle for one allocated physical frame; frees it on drop
+pub struct FrameTracker {
+    pub ppn: PhysPageNum,
+}
+
+impl FrameTracker {
+    /// the frame's contents as a mutable byte slice, split at page boundaries
+    /// by the caller the same way `translated_byte_buffer` splits user ranges
+    ///
+    /// Safe to hand out as `'static` because the backing `Box` never moves or
+    /// is freed for as long as this `FrameTracker` (or a clone of the `Arc`
+    /// wrapping it) is alive.
+    pub fn as_bytes_mut(&self) -> &'static mut [u8] {
+        let mut allocator = FRAME_ALLOCATOR.exclusive_access();
+        let bytes = allocator.bytes_mut(self.ppn);
+        unsafe { core::slice::from_raw_parts_mut(bytes.as_mut_ptr(), bytes.len()) }
+    }
+}
+
+impl Drop for FrameTracker {
+    fn drop(&mut self) {
+        FRAME_ALLOCATOR.exclusive_access().dealloc(self.ppn);
+    }
+}
+
+/// allocate one zeroed physical frame
+pub fn frame_alloc() -> Option<FrameTracker> {
+    FRAME_ALLOCATOR
+        .exclusive_access()
+        .alloc()
+        .map(|ppn| FrameTracker { ppn })
+}
@@ -0,0 +1,43 @@
+//! Address-space management: address types, physical frames, and per-task page tables
+mod address;
+mod file_backing;
+mod frame_allocator;
+mod page_table;
+mod vma;
+
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+
+use bitflags::bitflags;
+
+use crate::sync::UPSafeCell;
+
+pub use address::{PhysAddr, PhysPageNum, VPNRange, VirtAddr, VirtPageNum};
+pub use frame_allocator::{frame_alloc, FrameTracker};
+pub use page_table::{translated_byte_buffer, PageTable};
+pub use vma::{MapArea, MapSource};
+
+bitflags! {
+    /// page permission bits, mirroring the `prot` argument `sys_mmap` takes from user space
+    pub struct MapPermission: u8 {
+        const R = 1 << 0;
+        const W = 1 << 1;
+        const X = 1 << 2;
+        const U = 1 << 3;
+    }
+}
+
+lazy_static::lazy_static! {
+    /// every task's page table, keyed by the token `current_user_token()` hands out
+    static ref PAGE_TABLES: UPSafeCell<BTreeMap<usize, Arc<UPSafeCell<PageTable>>>> =
+        unsafe { UPSafeCell::new(BTreeMap::new()) };
+}
+
+/// look up (creating on first use) the page table belonging to `token`
+pub(crate) fn with_page_table(token: usize) -> Arc<UPSafeCell<PageTable>> {
+    let mut tables = PAGE_TABLES.exclusive_access();
+    tables
+        .entry(token)
+        .or_insert_with(|| Arc::new(unsafe { UPSafeCell::new(PageTable::new(token)) }))
+        .clone()
+}
@@ -0,0 +1,147 @@
+//! Per-task page table: virtual page number -> physical frame
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::config::PAGE_SIZE;
+use crate::sync::UPSafeCell;
+
+use super::address::{VirtAddr, VirtPageNum};
+use super::file_backing::shared_file_frame;
+use super::frame_allocator::{frame_alloc, FrameTracker};
+use super::vma::MapSource;
+use super::MapPermission;
+
+/// one task's page table, mapping its virtual pages to physical frames
+///
+/// `token` is the value `current_user_token()` hands to the syscall layer; a
+/// task's token never changes across its lifetime, so it doubles as the key
+/// used to look the table back up.
+pub struct PageTable {
+    pub token: usize,
+    frames: BTreeMap<VirtPageNum, Arc<FrameTracker>>,
+    perms: BTreeMap<VirtPageNum, MapPermission>,
+}
+
+impl PageTable {
+    pub(super) fn new(token: usize) -> Self {
+        Self {
+            token,
+            frames: BTreeMap::new(),
+            perms: BTreeMap::new(),
+        }
+    }
+
+    /// map `vpn` to a freshly allocated frame with the given permission,
+    /// returning the frame so callers can share/initialize its backing bytes
+    pub fn map(&mut self, vpn: VirtPageNum, perm: MapPermission) -> Option<Arc<FrameTracker>> {
+        let frame = Arc::new(frame_alloc()?);
+        self.perms.insert(vpn, perm);
+        self.frames.insert(vpn, frame.clone());
+        Some(frame)
+    }
+
+    /// map `vpn` to an already-allocated frame (e.g. one shared by a
+    /// `MAP_SHARED` file-backed mapping) rather than a freshly allocated one
+    pub fn map_shared(&mut self, vpn: VirtPageNum, frame: Arc<FrameTracker>, perm: MapPermission) {
+        self.perms.insert(vpn, perm);
+        self.frames.insert(vpn, frame);
+    }
+
+    /// drop `vpn`'s mapping, freeing the frame once nothing else shares it
+    pub fn unmap(&mut self, vpn: VirtPageNum) {
+        self.frames.remove(&vpn);
+        self.perms.remove(&vpn);
+    }
+
+    /// whether `vpn` currently has a mapping (i.e. the page has been faulted in)
+    pub fn is_mapped(&self, vpn: VirtPageNum) -> bool {
+        self.frames.contains_key(&vpn)
+    }
+
+    /// number of pages currently faulted in, i.e. this task's resident set size
+    pub fn mapped_frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    fn frame(&self, vpn: VirtPageNum) -> Option<&Arc<FrameTracker>> {
+        self.frames.get(&vpn)
+    }
+}
+
+/// translate a `[ptr, ptr + len)` user-space byte range into kernel-visible
+/// slices, one per physical page the range spans
+///
+/// Callers (e.g. `sys_get_time`, `sys_task_info`) copy into/out of each
+/// returned slice in turn, which is what makes the copy safe even when the
+/// struct being transferred straddles a page boundary. A first touch of any
+/// page lazily faults it in rather than trapping, since this checkout has no
+/// trap/page-fault handler to drive the fault-in from trap context: a page
+/// covered by a registered VMA (see `task::area_for`) is faulted in per that
+/// VMA's permission and backing source (sharing one frame per fd+page for
+/// `MAP_SHARED` file-backed areas); a page outside any VMA — e.g. a
+/// `sys_get_time`/`sys_task_info` output pointer, never mmap'd at all — is
+/// zero-filled read/write/user as before.
+pub fn translated_byte_buffer(token: usize, ptr: *const u8, len: usize) -> Vec<&'static mut [u8]> {
+    let page_table = super::with_page_table(token);
+    let mut start = ptr as usize;
+    let end = start + len;
+    let mut v = Vec::new();
+    while start < end {
+        let start_va = VirtAddr(start);
+        let mut vpn = start_va.floor();
+        let existing = {
+            let table = page_table.exclusive_access();
+            table.frame(vpn).cloned()
+        };
+        let frame = match existing {
+            Some(frame) => frame,
+            None => fault_in(&page_table, token, vpn),
+        };
+        let page_off = start_va.page_offset();
+        let bytes = frame.as_bytes_mut();
+        let chunk_end = (page_off + (end - start)).min(PAGE_SIZE);
+        vpn = vpn.next();
+        let end_va: VirtAddr = vpn.into();
+        start = end.min(end_va.0);
+        v.push(&mut bytes[page_off..chunk_end]);
+    }
+    v
+}
+
+/// fault `vpn` in for the first time, per the VMA covering it (if any)
+///
+/// A `MAP_SHARED` file-backed page is handed a frame shared by every mapping
+/// of the same fd+page via [`shared_file_frame`] and [`PageTable::map_shared`];
+/// any other VMA (anonymous, or a private file-backed mapping) gets a fresh
+/// zero-filled frame at its own permission; a `vpn` not covered by any VMA at
+/// all (e.g. a plain syscall output pointer) also gets a fresh zero-filled
+/// frame, at the permissive R|W|U this checkout has always used for that case.
+fn fault_in(
+    page_table: &Arc<UPSafeCell<PageTable>>,
+    token: usize,
+    vpn: VirtPageNum,
+) -> Arc<FrameTracker> {
+    let area = crate::task::area_for(token, vpn);
+    let mut table = page_table.exclusive_access();
+    match area {
+        Some(a) => match a.source {
+            MapSource::File {
+                fd,
+                offset,
+                shared: true,
+            } => {
+                let page_index = offset / PAGE_SIZE + (vpn.0 - a.vpn_range.start().0);
+                let frame = shared_file_frame(fd, page_index);
+                table.map_shared(vpn, frame.clone(), a.perm);
+                frame
+            }
+            _ => table
+                .map(vpn, a.perm)
+                .expect("out of physical frames while translating user pointer"),
+        },
+        None => table
+            .map(vpn, MapPermission::R | MapPermission::W | MapPermission::U)
+            .expect("out of physical frames while translating user pointer"),
+    }
+}
@@ -0,0 +1,39 @@
+//! Per-task virtual memory areas (VMAs): contiguous page ranges sharing one
+//! permission and backing source, replacing the earlier ad-hoc
+//! map/unmap/check-map trio with something `sys_mmap`'s fault-in path can
+//! actually consult
+use super::address::{VPNRange, VirtPageNum};
+use super::MapPermission;
+
+/// where a [`MapArea`]'s pages come from once faulted in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapSource {
+    /// zero-filled on first touch, private to this mapping
+    Anonymous,
+    /// backed by file descriptor `fd` starting at byte `offset`; `shared`
+    /// selects `MAP_SHARED` (one frame per fd+page, visible through every
+    /// mapping of it) vs `MAP_PRIVATE` (a private zero-filled frame)
+    File { fd: isize, offset: usize, shared: bool },
+}
+
+/// one virtual memory area in a task's address space
+#[derive(Clone, Debug)]
+pub struct MapArea {
+    pub vpn_range: VPNRange,
+    pub perm: MapPermission,
+    pub source: MapSource,
+}
+
+impl MapArea {
+    pub fn new(vpn_range: VPNRange, perm: MapPermission, source: MapSource) -> Self {
+        Self {
+            vpn_range,
+            perm,
+            source,
+        }
+    }
+
+    pub fn contains(&self, vpn: VirtPageNum) -> bool {
+        self.vpn_range.contains(vpn)
+    }
+}
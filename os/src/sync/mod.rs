@@ -0,0 +1,5 @@
+//! Synchronization primitives used by kernel-internal singletons
+
+mod up;
+
+pub use up::UPSafeCell;
@@ -0,0 +1,30 @@
+//! Uniprocessor-only interior mutability cell
+use core::cell::{RefCell, RefMut};
+
+/// Wraps a `RefCell` and only allows `exclusive_access` to borrow it mutably.
+///
+/// Safe to mark `Sync` because this kernel only ever runs one hart at a time
+/// with interrupts disabled while a singleton is held.
+pub struct UPSafeCell<T> {
+    inner: RefCell<T>,
+}
+
+unsafe impl<T> Sync for UPSafeCell<T> {}
+
+impl<T> UPSafeCell<T> {
+    /// Wrap `value` for single-hart exclusive access
+    ///
+    /// # Safety
+    /// The caller must guarantee this is never accessed concurrently from
+    /// more than one hart.
+    pub unsafe fn new(value: T) -> Self {
+        Self {
+            inner: RefCell::new(value),
+        }
+    }
+
+    /// Get the exclusive, mutable access to the inner value
+    pub fn exclusive_access(&self) -> RefMut<'_, T> {
+        self.inner.borrow_mut()
+    }
+}
@@ -0,0 +1,85 @@
+//! Syscall numbers and the dispatcher that records each call and enforces
+//! the calling task's seccomp policy before running its handler
+mod process;
+
+pub use process::*;
+
+use crate::task::{
+    exit_current_and_run_next, kstack_enter, kstack_exit, record_syscall, seccomp_check,
+    SeccompDefaultAction, SeccompVerdict,
+};
+
+/// read from a file descriptor (seccomp bookkeeping only: no handler in this
+/// trimmed kernel yet)
+pub const SYSCALL_READ: usize = 63;
+/// write to a file descriptor (seccomp bookkeeping only: no handler in this
+/// trimmed kernel yet)
+pub const SYSCALL_WRITE: usize = 64;
+/// [`sys_exit`]
+pub const SYSCALL_EXIT: usize = 93;
+/// [`sys_yield`]
+pub const SYSCALL_YIELD: usize = 124;
+/// [`sys_get_time`]
+pub const SYSCALL_GET_TIME: usize = 169;
+/// [`sys_set_priority`]
+pub const SYSCALL_SET_PRIORITY: usize = 140;
+/// [`sys_sbrk`]
+pub const SYSCALL_SBRK: usize = 214;
+/// [`sys_munmap`]
+pub const SYSCALL_MUNMAP: usize = 215;
+/// [`sys_mmap`]
+pub const SYSCALL_MMAP: usize = 222;
+/// [`sys_task_info`]
+pub const SYSCALL_TASK_INFO: usize = 410;
+/// [`sys_proc_stat`]
+pub const SYSCALL_PROC_STAT: usize = 411;
+/// [`sys_clock_gettime`]
+pub const SYSCALL_CLOCK_GETTIME: usize = 113;
+/// [`sys_clock_settime`]
+pub const SYSCALL_CLOCK_SETTIME: usize = 112;
+/// [`sys_ptrace`]
+pub const SYSCALL_PTRACE: usize = 101;
+/// [`sys_seccomp`]
+pub const SYSCALL_SECCOMP: usize = 277;
+
+/// dispatch `syscall_id` to its handler with register-style arguments
+///
+/// Every call is counted toward the calling task's `syscall_times`, checked
+/// against its seccomp policy (if any), and bracketed by `kstack_enter`/
+/// `kstack_exit` for `sys_proc_stat`'s kernel-stack accounting. The pid
+/// `kstack_enter` returns is threaded through explicitly (rather than asking
+/// `kstack_exit` to look up "the current task" again) so that a handler like
+/// `sys_yield`/`sys_exit` that reschedules mid-call still decrements the
+/// kstack depth of the task that actually entered the syscall, not whatever
+/// task happens to be current once it returns.
+pub fn syscall(syscall_id: usize, args: [usize; 6]) -> isize {
+    record_syscall(syscall_id);
+    if let SeccompVerdict::Deny(action) = seccomp_check(syscall_id) {
+        return match action {
+            SeccompDefaultAction::ReturnEperm => -1,
+            SeccompDefaultAction::Kill => {
+                exit_current_and_run_next();
+                unreachable!("exit_current_and_run_next never returns to the task it killed")
+            }
+        };
+    }
+    let pid = kstack_enter();
+    let ret = match syscall_id {
+        SYSCALL_EXIT => sys_exit(args[0] as i32),
+        SYSCALL_YIELD => sys_yield(),
+        SYSCALL_GET_TIME => sys_get_time(args[0] as *mut TimeVal, args[1]),
+        SYSCALL_TASK_INFO => sys_task_info(args[0] as *mut TaskInfo),
+        SYSCALL_PROC_STAT => sys_proc_stat(args[0] as *mut ProcStat),
+        SYSCALL_CLOCK_GETTIME => sys_clock_gettime(args[0], args[1] as *mut TimeVal),
+        SYSCALL_CLOCK_SETTIME => sys_clock_settime(args[0], args[1] as *const TimeVal),
+        SYSCALL_MMAP => sys_mmap(args[0], args[1], args[2], args[3], args[4] as isize, args[5]),
+        SYSCALL_MUNMAP => sys_munmap(args[0], args[1]),
+        SYSCALL_SET_PRIORITY => sys_set_priority(args[0] as isize),
+        SYSCALL_SBRK => sys_sbrk(args[0] as i32),
+        SYSCALL_PTRACE => sys_ptrace(args[0] as isize, args[1], args[2], args[3]),
+        SYSCALL_SECCOMP => sys_seccomp(args[0], args[1], args[2] as *const usize),
+        _ => -1,
+    };
+    kstack_exit(pid);
+    ret
+}
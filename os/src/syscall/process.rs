@@ -1,15 +1,20 @@
 //! Process management syscalls
 use core::mem;
 
+use alloc::vec::Vec;
+
 use crate::{
     config::MAX_SYSCALL_NUM,
-    mm::{translated_byte_buffer, MapPermission, VirtAddr},
+    mm::{translated_byte_buffer, MapPermission, MapSource, VirtAddr},
     task::{
-        change_program_brk, current_user_token, exit_current_and_run_next, get_task_status,
-        get_task_syscall_times, suspend_current_and_run_next, task_check_map, task_mmap,
-        task_unmap, TaskStatus,
+        change_program_brk, current_user_token, exit_current_and_run_next, get_child_count,
+        get_parent_pid, get_task_cpu_time_us, get_task_kstack_usage, get_task_rss,
+        get_task_status, get_task_stride, get_task_syscall_times, get_task_vsz, ptrace_attach,
+        ptrace_cont, ptrace_peek_token, ptrace_traceme, seccomp_set_filter, seccomp_set_strict,
+        set_task_priority, suspend_current_and_run_next, task_mmap_find, task_mmap_fixed,
+        task_munmap, SeccompDefaultAction, TaskStatus,
     },
-    timer::{get_time_ms, get_time_us},
+    timer::{get_realtime_us, get_time_ms, get_time_us, set_realtime_us},
 };
 
 #[repr(C)]
@@ -28,6 +33,10 @@ pub struct TaskInfo {
     syscall_times: [u32; MAX_SYSCALL_NUM],
     /// Total running time of task
     time: usize,
+    /// stride-scheduling priority, as last set by `sys_set_priority`
+    priority: u64,
+    /// accumulated stride-scheduling pass
+    pass: u64,
 }
 
 /// task exits and submit an exit code
@@ -44,18 +53,20 @@ pub fn sys_yield() -> isize {
     0
 }
 
-/// YOUR JOB: get time with second and microsecond
-/// HINT: You might reimplement it with virtual memory management.
-/// HINT: What if [`TimeVal`] is splitted by two pages ?
+/// get time with second and microsecond
+///
+/// Copied out via `translated_byte_buffer` so it's safe even if [`TimeVal`] is
+/// split across two pages.
 pub fn sys_get_time(ts: *mut TimeVal, _tz: usize) -> isize {
     trace!("kernel: sys_get_time");
 
     let us = get_time_us();
-    let k_ts = &TimeVal {
+    let k_ts = TimeVal {
         sec: us / 1_000_000,
         usec: us % 1_000_000,
-    } as *const TimeVal
-        as *const [u8; mem::size_of::<TimeVal>() / mem::size_of::<u8>()];
+    };
+    let k_ts: &[u8; mem::size_of::<TimeVal>()] =
+        unsafe { &*(&k_ts as *const TimeVal as *const [u8; mem::size_of::<TimeVal>()]) };
 
     let token = current_user_token();
     let u_ts = translated_byte_buffer(token, ts as *const u8, mem::size_of::<TimeVal>());
@@ -63,26 +74,29 @@ pub fn sys_get_time(ts: *mut TimeVal, _tz: usize) -> isize {
     let mut begin = 0;
     for buffer in u_ts {
         let len = buffer.len();
-        unsafe {
-            buffer.copy_from_slice(&(*k_ts)[begin..len]);
-            begin += len;
-        }
+        buffer.copy_from_slice(&k_ts[begin..begin + len]);
+        begin += len;
     }
     0
 }
 
-/// YOUR JOB: Finish sys_task_info to pass testcases
-/// HINT: You might reimplement it with virtual memory management.
-/// HINT: What if [`TaskInfo`] is splitted by two pages ?
+/// report the calling task's status, per-syscall invocation counts,
+/// milliseconds since boot, and stride-scheduling priority/pass
+///
+/// Copied out the same two-page-safe way as [`sys_get_time`].
 pub fn sys_task_info(ti: *mut TaskInfo) -> isize {
     trace!("kernel: sys_task_info!");
 
-    let k_ti = &TaskInfo {
+    let (priority, pass) = get_task_stride();
+    let k_ti = TaskInfo {
         status: get_task_status(),
         syscall_times: get_task_syscall_times(),
         time: get_time_ms(),
-    } as *const TaskInfo
-        as *const [u8; mem::size_of::<TaskInfo>() / mem::size_of::<u8>()];
+        priority,
+        pass,
+    };
+    let k_ti: &[u8; mem::size_of::<TaskInfo>()] =
+        unsafe { &*(&k_ti as *const TaskInfo as *const [u8; mem::size_of::<TaskInfo>()]) };
 
     let token = current_user_token();
     let u_ti = translated_byte_buffer(token, ti as *const u8, mem::size_of::<TaskInfo>());
@@ -90,62 +104,247 @@ pub fn sys_task_info(ti: *mut TaskInfo) -> isize {
     let mut begin = 0;
     for buffer in u_ti {
         let len = buffer.len();
-        unsafe {
-            buffer.copy_from_slice(&(*k_ti)[begin..len]);
-            begin += len;
-        }
+        buffer.copy_from_slice(&k_ti[begin..begin + len]);
+        begin += len;
     }
     0
 }
 
-// YOUR JOB: Implement mmap.
-pub fn sys_mmap(start: usize, len: usize, port: usize) -> isize {
-    trace!("kernel: sys_mmap!");
-    let v_start = VirtAddr(start);
-    let v_end = VirtAddr(start + len);
+/// wall-clock time, adjustable via `sys_clock_settime`
+pub const CLOCK_REALTIME: usize = 0;
+/// time since boot, never adjusted
+pub const CLOCK_MONOTONIC: usize = 1;
+/// CPU time actually consumed by the calling task, accumulated from
+/// scheduler bookkeeping rather than wall time
+pub const CLOCK_PROCESS_CPUTIME: usize = 2;
 
-    if !v_start.aligned() {
+/// read one of [`CLOCK_REALTIME`]/[`CLOCK_MONOTONIC`]/[`CLOCK_PROCESS_CPUTIME`]
+///
+/// Copied out the same two-page-safe way as `sys_get_time`.
+pub fn sys_clock_gettime(clock_id: usize, ts: *mut TimeVal) -> isize {
+    trace!("kernel: sys_clock_gettime");
+
+    let us = match clock_id {
+        CLOCK_MONOTONIC => get_time_us() as i64,
+        CLOCK_REALTIME => get_realtime_us(),
+        CLOCK_PROCESS_CPUTIME => get_task_cpu_time_us() as i64,
+        _ => return -1,
+    };
+    let Ok(us) = usize::try_from(us) else {
         return -1;
+    };
+    let k_ts = TimeVal {
+        sec: us / 1_000_000,
+        usec: us % 1_000_000,
+    };
+    let k_ts: &[u8; mem::size_of::<TimeVal>()] =
+        unsafe { &*(&k_ts as *const TimeVal as *const [u8; mem::size_of::<TimeVal>()]) };
+
+    let token = current_user_token();
+    let u_ts = translated_byte_buffer(token, ts as *const u8, mem::size_of::<TimeVal>());
+    let mut begin = 0;
+    for buffer in u_ts {
+        let len = buffer.len();
+        buffer.copy_from_slice(&k_ts[begin..begin + len]);
+        begin += len;
     }
+    0
+}
 
-    let mut map_permission = MapPermission::U;
-    if port & !0x7 != 0 || port & 0x7 == 0 {
+/// set [`CLOCK_REALTIME`]'s offset from the monotonic clock so it reports
+/// `*ts`; any other `clock_id` is rejected, matching `CLOCK_MONOTONIC` and
+/// `CLOCK_PROCESS_CPUTIME` being derived rather than freely settable
+pub fn sys_clock_settime(clock_id: usize, ts: *const TimeVal) -> isize {
+    trace!("kernel: sys_clock_settime");
+    if clock_id != CLOCK_REALTIME {
         return -1;
     }
-    if port & 0x1 != 0 {
-        map_permission |= MapPermission::R;
+
+    let mut k_ts = TimeVal { sec: 0, usec: 0 };
+    let k_buf: &mut [u8; mem::size_of::<TimeVal>()] =
+        unsafe { &mut *(&mut k_ts as *mut TimeVal as *mut [u8; mem::size_of::<TimeVal>()]) };
+    let token = current_user_token();
+    let src = translated_byte_buffer(token, ts as *const u8, mem::size_of::<TimeVal>());
+    let mut begin = 0;
+    for buffer in src {
+        let len = buffer.len();
+        k_buf[begin..begin + len].copy_from_slice(buffer);
+        begin += len;
+    }
+
+    let Some(now_us) = i64::try_from(k_ts.sec)
+        .ok()
+        .and_then(|sec| sec.checked_mul(1_000_000))
+        .and_then(|sec_us| sec_us.checked_add(k_ts.usec as i64))
+    else {
+        return -1;
+    };
+    set_realtime_us(now_us);
+    0
+}
+
+/// a `ps`-like snapshot of the calling task, broader than [`TaskInfo`]: full
+/// status taxonomy, parent/child relationships, and memory/kernel-stack usage
+#[repr(C)]
+#[allow(dead_code)]
+pub struct ProcStat {
+    /// Task status in it's life cycle
+    status: TaskStatus,
+    /// The numbers of syscall called by task
+    syscall_times: [u32; MAX_SYSCALL_NUM],
+    /// milliseconds since boot, the same reading `sys_task_info` reports
+    time: usize,
+    /// stride-scheduling priority, as last set by `sys_set_priority`
+    priority: u64,
+    /// accumulated stride-scheduling pass
+    pass: u64,
+    /// parent's pid, or `-1` if this task has none
+    parent_pid: isize,
+    /// number of tasks whose parent is this one
+    child_count: usize,
+    /// sum of this task's VMA extents, in bytes
+    vsz: usize,
+    /// number of physical frames currently faulted in, in pages
+    rss: usize,
+    /// estimated kernel-stack usage, in bytes
+    kstack_usage: usize,
+}
+
+/// report a fuller `ps`-like view of the calling task than [`sys_task_info`]:
+/// status (including `Sleeping`/`Stopped`), parent pid, child count, virtual
+/// memory size, resident set size, and kernel-stack usage
+///
+/// RSS/VSZ are computed by walking the task's VMA list and page table at call
+/// time rather than tracked incrementally. Copied out the same two-page-safe
+/// way as `sys_get_time`/`sys_task_info`.
+pub fn sys_proc_stat(ps: *mut ProcStat) -> isize {
+    trace!("kernel: sys_proc_stat");
+
+    let (priority, pass) = get_task_stride();
+    let k_ps = ProcStat {
+        status: get_task_status(),
+        syscall_times: get_task_syscall_times(),
+        time: get_time_ms(),
+        priority,
+        pass,
+        parent_pid: get_parent_pid().map(|pid| pid as isize).unwrap_or(-1),
+        child_count: get_child_count(),
+        vsz: get_task_vsz(),
+        rss: get_task_rss(),
+        kstack_usage: get_task_kstack_usage(),
+    };
+    let k_ps: &[u8; mem::size_of::<ProcStat>()] =
+        unsafe { &*(&k_ps as *const ProcStat as *const [u8; mem::size_of::<ProcStat>()]) };
+
+    let token = current_user_token();
+    let u_ps = translated_byte_buffer(token, ps as *const u8, mem::size_of::<ProcStat>());
+
+    let mut begin = 0;
+    for buffer in u_ps {
+        let len = buffer.len();
+        buffer.copy_from_slice(&k_ps[begin..begin + len]);
+        begin += len;
     }
-    if port & 0x2 != 0 {
-        map_permission |= MapPermission::W;
+    0
+}
+
+/// writes are visible through every other `MAP_SHARED` mapping of the same
+/// `fd`+page, which all share one frame
+pub const MAP_SHARED: usize = 0x01;
+/// writes are private to this mapping (copy-on-write semantics are not
+/// modeled: a private file-backed page is simply a private zero-filled one)
+pub const MAP_PRIVATE: usize = 0x02;
+/// place the mapping at exactly `addr`, failing if that would overlap an
+/// existing one, instead of letting the kernel choose a free address
+pub const MAP_FIXED: usize = 0x10;
+/// the mapping has no backing file; `fd`/`offset` are ignored
+pub const MAP_ANONYMOUS: usize = 0x20;
+
+/// map `len` bytes into the calling task's address space
+///
+/// `prot` is the same R/W/X permission bits `port` used before (bit 0 = R,
+/// bit 1 = W, bit 2 = X). `flags` must set exactly one of [`MAP_SHARED`]/
+/// [`MAP_PRIVATE`], and [`MAP_ANONYMOUS`] or a non-negative `fd` for a
+/// file-backed mapping. If [`MAP_FIXED`] is set, or `addr` is non-zero, the
+/// mapping is placed at exactly `addr`; otherwise the kernel searches the
+/// task's address space for a free gap and returns its base. Pages are not
+/// touched here: they're zero-filled (or, for a `MAP_SHARED` file-backed
+/// mapping, handed a frame shared with every other mapping of the same
+/// `fd`+page) lazily on first access, in `translated_byte_buffer`'s fault-in
+/// path.
+pub fn sys_mmap(addr: usize, len: usize, prot: usize, flags: usize, fd: isize, offset: usize) -> isize {
+    trace!("kernel: sys_mmap");
+    if len == 0 || prot & !0x7 != 0 || prot & 0x7 == 0 {
+        return -1;
     }
-    if port & 0x4 != 0 {
-        map_permission |= MapPermission::X;
+
+    let shared = flags & MAP_SHARED != 0;
+    let private = flags & MAP_PRIVATE != 0;
+    if shared == private {
+        return -1;
     }
-    if !task_check_map(v_start, v_end, false) {
+
+    let anonymous = flags & MAP_ANONYMOUS != 0;
+    if !anonymous && fd < 0 {
         return -1;
     }
 
-    task_mmap(v_start, v_end, map_permission);
-    0
+    let mut perm = MapPermission::U;
+    if prot & 0x1 != 0 {
+        perm |= MapPermission::R;
+    }
+    if prot & 0x2 != 0 {
+        perm |= MapPermission::W;
+    }
+    if prot & 0x4 != 0 {
+        perm |= MapPermission::X;
+    }
+
+    let source = if anonymous {
+        MapSource::Anonymous
+    } else {
+        MapSource::File { fd, offset, shared }
+    };
+
+    if flags & MAP_FIXED != 0 || addr != 0 {
+        let v_start = VirtAddr(addr);
+        if !v_start.aligned() {
+            return -1;
+        }
+        if !task_mmap_fixed(v_start, VirtAddr(addr + len), perm, source) {
+            return -1;
+        }
+        addr as isize
+    } else {
+        task_mmap_find(len, perm, source).0 as isize
+    }
 }
 
-// YOUR JOB: Implement munmap.
+/// unmap `[start, start + len)` from the calling task's address space,
+/// splitting any VMA that only partially overlaps it
 pub fn sys_munmap(start: usize, len: usize) -> isize {
-    trace!("kernel: sys_munmap!");
+    trace!("kernel: sys_munmap");
     let v_start = VirtAddr(start);
-    let v_end = VirtAddr(start + len);
-    
     if !v_start.aligned() {
         return -1;
     }
 
-    if !task_check_map(v_start, v_end, true) {
-        return -1;
+    if task_munmap(v_start, VirtAddr(start + len)) {
+        0
+    } else {
+        -1
     }
+}
 
-    task_unmap(v_start, v_end);
-    0
+/// set the calling task's stride-scheduling priority; `prio` must be `>= 2`
+pub fn sys_set_priority(prio: isize) -> isize {
+    trace!("kernel: sys_set_priority");
+    if prio < 2 {
+        return -1;
+    }
+    set_task_priority(prio as u64)
 }
+
 /// change data segment size
 pub fn sys_sbrk(size: i32) -> isize {
     trace!("kernel: sys_sbrk");
@@ -155,3 +354,112 @@ pub fn sys_sbrk(size: i32) -> isize {
         -1
     }
 }
+
+/// the calling task requests that its parent become its tracer
+pub const PTRACE_TRACEME: isize = 0;
+/// read one word at `addr` in the tracee's address space
+pub const PTRACE_PEEKTEXT: isize = 1;
+/// write `data` as one word at `addr` in the tracee's address space
+pub const PTRACE_POKETEXT: isize = 4;
+/// resume a stopped tracee, optionally delivering `data` as a signal
+pub const PTRACE_CONT: isize = 7;
+/// stop task `pid` and become its tracer
+pub const PTRACE_ATTACH: isize = 16;
+
+/// trace and control another task's execution
+///
+/// Supports `PTRACE_TRACEME`, `PTRACE_ATTACH`, `PTRACE_CONT` and the
+/// `PTRACE_PEEKTEXT`/`PTRACE_POKETEXT` word-at-a-time memory accesses, resolved
+/// against the tracee's own page table rather than the caller's.
+pub fn sys_ptrace(request: isize, pid: usize, addr: usize, data: usize) -> isize {
+    trace!("kernel: sys_ptrace");
+    match request {
+        PTRACE_TRACEME => ptrace_traceme(),
+        PTRACE_ATTACH => ptrace_attach(pid),
+        PTRACE_CONT => ptrace_cont(pid, data as u32),
+        PTRACE_PEEKTEXT => {
+            let Some(token) = ptrace_peek_token(pid) else {
+                return -1;
+            };
+            let mut word = 0usize;
+            let k_word: &mut [u8; mem::size_of::<usize>()] =
+                unsafe { &mut *(&mut word as *mut usize as *mut [u8; mem::size_of::<usize>()]) };
+            let src = translated_byte_buffer(token, addr as *const u8, mem::size_of::<usize>());
+            let mut begin = 0;
+            for buffer in src {
+                let len = buffer.len();
+                k_word[begin..begin + len].copy_from_slice(buffer);
+                begin += len;
+            }
+            word as isize
+        }
+        PTRACE_POKETEXT => {
+            let Some(token) = ptrace_peek_token(pid) else {
+                return -1;
+            };
+            let k_word: &[u8; mem::size_of::<usize>()] =
+                unsafe { &*(&data as *const usize as *const [u8; mem::size_of::<usize>()]) };
+            let dst = translated_byte_buffer(token, addr as *const u8, mem::size_of::<usize>());
+            let mut begin = 0;
+            for buffer in dst {
+                let len = buffer.len();
+                buffer.copy_from_slice(&k_word[begin..begin + len]);
+                begin += len;
+            }
+            0
+        }
+        _ => -1,
+    }
+}
+
+/// only `exit`/`yield`/`read`/`write` are permitted
+pub const SECCOMP_MODE_STRICT: usize = 1;
+/// the task-supplied array at `filter_ptr` is the complete allow-list
+pub const SECCOMP_MODE_FILTER: usize = 2;
+/// OR this into `mode` to kill a task that trips the filter instead of
+/// returning `-1` to it
+pub const SECCOMP_DEFAULT_KILL: usize = 0x1000;
+
+/// install a sticky seccomp policy restricting which syscalls the calling
+/// task (and, once this kernel grows a fork/spawn syscall, its children) may
+/// invoke
+///
+/// `mode` is [`SECCOMP_MODE_STRICT`] or [`SECCOMP_MODE_FILTER`], optionally
+/// OR'd with [`SECCOMP_DEFAULT_KILL`]. In filter mode, `flags` is the number
+/// of `usize` syscall numbers at `filter_ptr`. A policy can only ever be
+/// installed once per task.
+pub fn sys_seccomp(mode: usize, flags: usize, filter_ptr: *const usize) -> isize {
+    trace!("kernel: sys_seccomp");
+    let default_action = if mode & SECCOMP_DEFAULT_KILL != 0 {
+        SeccompDefaultAction::Kill
+    } else {
+        SeccompDefaultAction::ReturnEperm
+    };
+    match mode & !SECCOMP_DEFAULT_KILL {
+        SECCOMP_MODE_STRICT => seccomp_set_strict(default_action),
+        SECCOMP_MODE_FILTER => {
+            if flags > MAX_SYSCALL_NUM {
+                return -1;
+            }
+            let Some(byte_len) = flags.checked_mul(mem::size_of::<usize>()) else {
+                return -1;
+            };
+
+            let token = current_user_token();
+            let src = translated_byte_buffer(token, filter_ptr as *const u8, byte_len);
+            let mut raw = alloc::vec![0u8; byte_len];
+            let mut begin = 0;
+            for buffer in src {
+                let len = buffer.len();
+                raw[begin..begin + len].copy_from_slice(buffer);
+                begin += len;
+            }
+            let allowed: Vec<usize> = raw
+                .chunks_exact(mem::size_of::<usize>())
+                .map(|chunk| usize::from_ne_bytes(chunk.try_into().unwrap()))
+                .collect();
+            seccomp_set_filter(&allowed, default_action)
+        }
+        _ => -1,
+    }
+}
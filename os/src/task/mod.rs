@@ -0,0 +1,450 @@
+//! Task management: the task control block, the scheduler, and the
+//! syscall-facing helpers `os/src/syscall/process.rs` calls into
+mod task;
+
+use alloc::collections::BTreeMap;
+
+use crate::config::{BIG_STRIDE, KSTACK_FRAME_ESTIMATE, MAX_SYSCALL_NUM, MMAP_BASE, PAGE_SIZE};
+use crate::mm::{self, MapArea, MapPermission, MapSource, VPNRange, VirtAddr, VirtPageNum};
+use crate::sync::UPSafeCell;
+
+pub use task::{PtraceState, SeccompDefaultAction, StrideState, TaskControlBlock, TaskStatus};
+
+struct TaskManager {
+    tasks: BTreeMap<usize, TaskControlBlock>,
+    current: usize,
+}
+
+impl TaskManager {
+    fn new() -> Self {
+        let mut tasks = BTreeMap::new();
+        tasks.insert(0, TaskControlBlock::new(0, None));
+        let mut inner = Self { tasks, current: 0 };
+        let task = inner.tasks.get_mut(&0).unwrap();
+        task.status = TaskStatus::Running;
+        task.accounting_start_us = crate::timer::get_time_us();
+        inner
+    }
+
+    fn current(&self) -> &TaskControlBlock {
+        self.tasks.get(&self.current).unwrap()
+    }
+
+    fn current_mut(&mut self) -> &mut TaskControlBlock {
+        self.tasks.get_mut(&self.current).unwrap()
+    }
+
+    /// stride scheduling: hand the CPU to the runnable task with the
+    /// smallest accumulated pass, then advance that task's pass by
+    /// `BIG_STRIDE / priority`
+    fn schedule_next(&mut self) {
+        let next_pid = self
+            .tasks
+            .values()
+            .filter(|t| t.status == TaskStatus::Ready)
+            .min_by(|a, b| stride_precedes(a.stride.pass, b.stride.pass))
+            .map(|t| t.pid);
+        // Nothing else runnable: keep running whatever is current so the
+        // simulated kernel always has a task to charge syscalls against.
+        let Some(pid) = next_pid else {
+            return;
+        };
+        let task = self.tasks.get_mut(&pid).unwrap();
+        task.status = TaskStatus::Running;
+        task.stride.pass = task.stride.pass.wrapping_add(BIG_STRIDE / task.stride.priority);
+        task.accounting_start_us = crate::timer::get_time_us();
+        self.current = pid;
+    }
+
+    /// charge the currently-running task's CPU time for the interval since
+    /// it was last switched in, called just before it's switched out
+    fn charge_current_cpu_time(&mut self) {
+        let now = crate::timer::get_time_us();
+        let task = self.current_mut();
+        task.cpu_time_us += now.saturating_sub(task.accounting_start_us);
+        task.accounting_start_us = now;
+    }
+}
+
+/// wraparound-safe "does stride `a` precede `b`" ordering, valid as long as
+/// `max(pass) - min(pass) <= BIG_STRIDE / 2` holds across all tasks, the
+/// invariant stride scheduling maintains
+fn stride_precedes(a: u64, b: u64) -> core::cmp::Ordering {
+    (a.wrapping_sub(b) as i64).cmp(&0)
+}
+
+lazy_static::lazy_static! {
+    static ref TASK_MANAGER: UPSafeCell<TaskManager> = unsafe { UPSafeCell::new(TaskManager::new()) };
+}
+
+/// the calling task's page-table token
+pub fn current_user_token() -> usize {
+    TASK_MANAGER.exclusive_access().current().token()
+}
+
+/// the calling task's current status
+pub fn get_task_status() -> TaskStatus {
+    TASK_MANAGER.exclusive_access().current().status
+}
+
+/// the calling task's per-syscall invocation counters
+pub fn get_task_syscall_times() -> [u32; MAX_SYSCALL_NUM] {
+    TASK_MANAGER.exclusive_access().current().syscall_times
+}
+
+/// the calling task's current stride-scheduling priority and accumulated pass
+pub fn get_task_stride() -> (u64, u64) {
+    let manager = TASK_MANAGER.exclusive_access();
+    let task = manager.current();
+    (task.stride.priority, task.stride.pass)
+}
+
+/// set the calling task's stride-scheduling priority, returning the new
+/// priority, or `-1` if `prio < 2`
+pub fn set_task_priority(prio: u64) -> isize {
+    if prio < 2 {
+        return -1;
+    }
+    TASK_MANAGER.exclusive_access().current_mut().stride.priority = prio;
+    prio as isize
+}
+
+/// the calling task's parent pid, if any
+pub fn get_parent_pid() -> Option<usize> {
+    TASK_MANAGER.exclusive_access().current().parent
+}
+
+/// number of tasks whose `parent` is the calling task
+pub fn get_child_count() -> usize {
+    let manager = TASK_MANAGER.exclusive_access();
+    let pid = manager.current;
+    manager.tasks.values().filter(|t| t.parent == Some(pid)).count()
+}
+
+/// sum of the calling task's VMA extents, in bytes (its virtual memory size)
+pub fn get_task_vsz() -> usize {
+    let manager = TASK_MANAGER.exclusive_access();
+    manager
+        .current()
+        .areas
+        .iter()
+        .map(|a| a.vpn_range.len() * PAGE_SIZE)
+        .sum()
+}
+
+/// number of physical frames currently faulted in for the calling task (its
+/// resident set size, in pages)
+pub fn get_task_rss() -> usize {
+    let token = current_user_token();
+    mm::with_page_table(token).exclusive_access().mapped_frame_count()
+}
+
+/// CPU time actually consumed by the calling task so far, in microseconds
+/// (`CLOCK_PROCESS_CPUTIME`), including time since it was last switched in if
+/// it's still running
+pub fn get_task_cpu_time_us() -> usize {
+    let manager = TASK_MANAGER.exclusive_access();
+    let task = manager.current();
+    let now = crate::timer::get_time_us();
+    task.cpu_time_us + now.saturating_sub(task.accounting_start_us)
+}
+
+/// estimated kernel-stack usage of the calling task, in bytes
+pub fn get_task_kstack_usage() -> usize {
+    TASK_MANAGER.exclusive_access().current().kstack_depth as usize * KSTACK_FRAME_ESTIMATE
+}
+
+/// record that the calling task has entered a syscall, returning its pid so
+/// the matching `kstack_exit` targets the same task even if scheduling
+/// changes which task is `current` before the syscall returns (e.g. a
+/// `sys_yield`/`sys_exit` inside the handler)
+pub fn kstack_enter() -> usize {
+    let mut manager = TASK_MANAGER.exclusive_access();
+    let pid = manager.current;
+    manager.current_mut().kstack_depth += 1;
+    pid
+}
+
+/// record that task `pid` — not necessarily whichever task is `current` now
+/// — has left a syscall
+pub fn kstack_exit(pid: usize) {
+    let mut manager = TASK_MANAGER.exclusive_access();
+    if let Some(task) = manager.tasks.get_mut(&pid) {
+        task.kstack_depth = task.kstack_depth.saturating_sub(1);
+    }
+}
+
+/// count one invocation of `syscall_id` toward the calling task's
+/// `syscall_times`, called by the dispatcher before running each handler
+pub fn record_syscall(syscall_id: usize) {
+    if syscall_id >= MAX_SYSCALL_NUM {
+        return;
+    }
+    TASK_MANAGER.exclusive_access().current_mut().syscall_times[syscall_id] += 1;
+}
+
+/// outcome of checking a syscall number against the calling task's seccomp
+/// policy
+pub enum SeccompVerdict {
+    /// the call may proceed
+    Allow,
+    /// the call is not in the task's allow-list; act per `SeccompDefaultAction`
+    Deny(SeccompDefaultAction),
+}
+
+/// consult the calling task's seccomp policy for `syscall_id`
+pub fn seccomp_check(syscall_id: usize) -> SeccompVerdict {
+    let manager = TASK_MANAGER.exclusive_access();
+    let task = manager.current();
+    if !task.seccomp.active || task.seccomp.allowed.get(syscall_id).copied().unwrap_or(false) {
+        SeccompVerdict::Allow
+    } else {
+        SeccompVerdict::Deny(task.seccomp.default_action)
+    }
+}
+
+/// install the "strict" seccomp policy (only exit/yield/read/write
+/// permitted) for the calling task
+///
+/// Sticky: fails if the task already has a policy installed.
+pub fn seccomp_set_strict(default_action: SeccompDefaultAction) -> isize {
+    use crate::syscall::{SYSCALL_EXIT, SYSCALL_READ, SYSCALL_WRITE, SYSCALL_YIELD};
+    seccomp_install(&[SYSCALL_EXIT, SYSCALL_YIELD, SYSCALL_READ, SYSCALL_WRITE], default_action)
+}
+
+/// install a "filter" seccomp policy permitting exactly the syscall numbers
+/// in `allowed` for the calling task
+///
+/// Sticky: fails if the task already has a policy installed.
+pub fn seccomp_set_filter(allowed: &[usize], default_action: SeccompDefaultAction) -> isize {
+    seccomp_install(allowed, default_action)
+}
+
+fn seccomp_install(allowed: &[usize], default_action: SeccompDefaultAction) -> isize {
+    let mut manager = TASK_MANAGER.exclusive_access();
+    let task = manager.current_mut();
+    if task.seccomp.active {
+        return -1;
+    }
+    let mut bitmap = [false; MAX_SYSCALL_NUM];
+    for &num in allowed {
+        if num >= MAX_SYSCALL_NUM {
+            return -1;
+        }
+        bitmap[num] = true;
+    }
+    task.seccomp.active = true;
+    task.seccomp.allowed = bitmap;
+    task.seccomp.default_action = default_action;
+    0
+}
+
+/// grow or shrink the calling task's program break by `size` bytes, returning
+/// the break's previous value
+pub fn change_program_brk(size: i32) -> Option<usize> {
+    let mut manager = TASK_MANAGER.exclusive_access();
+    let task = manager.current_mut();
+    let old_brk = task.program_brk;
+    let new_brk = old_brk as isize + size as isize;
+    if new_brk < 0 {
+        return None;
+    }
+    task.program_brk = new_brk as usize;
+    Some(old_brk)
+}
+
+/// the calling task exits and control passes to the next runnable task
+pub fn exit_current_and_run_next() {
+    let mut manager = TASK_MANAGER.exclusive_access();
+    manager.charge_current_cpu_time();
+    let pid = manager.current;
+    manager.current_mut().status = TaskStatus::Zombie;
+    // A tracer that dies mid-session shouldn't leave its tracee parked
+    // forever with no one able to `PTRACE_CONT` it.
+    let tracees: alloc::vec::Vec<usize> = manager
+        .tasks
+        .values()
+        .filter(|t| t.ptrace.tracer == Some(pid))
+        .map(|t| t.pid)
+        .collect();
+    for tracee in tracees {
+        let task = manager.tasks.get_mut(&tracee).unwrap();
+        task.ptrace.tracer = None;
+        if task.ptrace.stop_requested {
+            task.ptrace.stop_requested = false;
+            task.status = TaskStatus::Ready;
+        }
+    }
+    manager.schedule_next();
+}
+
+/// the calling task gives up the CPU for other tasks
+///
+/// A task that is itself the target of a pending traced-stop (e.g. it was
+/// just `PTRACE_ATTACH`ed) parks as `Stopped` instead of going back on the
+/// ready queue, and stays there until its tracer issues `PTRACE_CONT`.
+pub fn suspend_current_and_run_next() {
+    let mut manager = TASK_MANAGER.exclusive_access();
+    manager.charge_current_cpu_time();
+    if manager.current().ptrace.stop_requested {
+        manager.current_mut().status = TaskStatus::Stopped;
+    } else {
+        manager.current_mut().status = TaskStatus::Ready;
+    }
+    manager.schedule_next();
+}
+
+/// mark the calling task as traceable, recording its parent as tracer
+pub fn ptrace_traceme() -> isize {
+    let mut manager = TASK_MANAGER.exclusive_access();
+    let parent = manager.current().parent;
+    let Some(parent) = parent else {
+        return -1;
+    };
+    manager.current_mut().ptrace.tracer = Some(parent);
+    0
+}
+
+/// stop task `pid` and become its tracer
+pub fn ptrace_attach(pid: usize) -> isize {
+    let mut manager = TASK_MANAGER.exclusive_access();
+    let tracer = manager.current;
+    let Some(target) = manager.tasks.get_mut(&pid) else {
+        return -1;
+    };
+    if target.pid == tracer {
+        return -1;
+    }
+    target.ptrace.tracer = Some(tracer);
+    target.ptrace.stop_requested = true;
+    if target.status != TaskStatus::Zombie {
+        target.status = TaskStatus::Stopped;
+    }
+    0
+}
+
+/// resume a stopped tracee, optionally delivering `sig`
+pub fn ptrace_cont(pid: usize, sig: u32) -> isize {
+    let mut manager = TASK_MANAGER.exclusive_access();
+    let tracer = manager.current;
+    let Some(target) = manager.tasks.get_mut(&pid) else {
+        return -1;
+    };
+    if target.ptrace.tracer != Some(tracer) {
+        return -1;
+    }
+    target.ptrace.stop_requested = false;
+    target.ptrace.pending_signal = if sig == 0 { None } else { Some(sig) };
+    if target.status == TaskStatus::Stopped {
+        target.status = TaskStatus::Ready;
+    }
+    0
+}
+
+/// resolve the page-table token for a peek/poke against task `pid`
+///
+/// Only the tracer of `pid` (or `pid` peeking its own memory) may resolve it.
+pub fn ptrace_peek_token(pid: usize) -> Option<usize> {
+    let manager = TASK_MANAGER.exclusive_access();
+    let caller = manager.current;
+    let target = manager.tasks.get(&pid)?;
+    if pid != caller && target.ptrace.tracer != Some(caller) {
+        return None;
+    }
+    Some(target.token())
+}
+
+/// the VMA covering `vpn` in the task identified by `token`, if any
+///
+/// Called from `mm::page_table`'s lazy fault-in path so a faulting address
+/// is backed per the permission/source the owning mapping was actually
+/// registered with, rather than a one-size-fits-all default.
+pub fn area_for(token: usize, vpn: VirtPageNum) -> Option<MapArea> {
+    TASK_MANAGER
+        .exclusive_access()
+        .tasks
+        .get(&token)?
+        .areas
+        .iter()
+        .find(|a| a.contains(vpn))
+        .cloned()
+}
+
+/// register a VMA covering exactly `[v_start, v_end)`, failing if it would
+/// overlap one the calling task already has
+pub fn task_mmap_fixed(
+    v_start: VirtAddr,
+    v_end: VirtAddr,
+    perm: MapPermission,
+    source: MapSource,
+) -> bool {
+    let range = VPNRange::new(v_start.floor(), v_end.ceil());
+    let mut manager = TASK_MANAGER.exclusive_access();
+    let task = manager.current_mut();
+    if task.areas.iter().any(|a| a.vpn_range.overlaps(&range)) {
+        return false;
+    }
+    task.areas.push(MapArea::new(range, perm, source));
+    true
+}
+
+/// find a free, page-aligned gap of at least `len` bytes in the calling
+/// task's address space (searching upward from `MMAP_BASE`), register a VMA
+/// covering it, and return the chosen base address
+pub fn task_mmap_find(len: usize, perm: MapPermission, source: MapSource) -> VirtAddr {
+    let npages = VirtAddr(len).ceil().0;
+    let mut manager = TASK_MANAGER.exclusive_access();
+    let task = manager.current_mut();
+
+    let mut sorted: alloc::vec::Vec<&MapArea> = task.areas.iter().collect();
+    sorted.sort_by_key(|a| a.vpn_range.start().0);
+
+    let mut candidate = VirtAddr(MMAP_BASE).floor();
+    for area in sorted {
+        if candidate.0 + npages <= area.vpn_range.start().0 {
+            break;
+        }
+        candidate = core::cmp::max(candidate, area.vpn_range.end());
+    }
+
+    let range = VPNRange::new(candidate, VirtPageNum(candidate.0 + npages));
+    task.areas.push(MapArea::new(range, perm, source));
+    candidate.into()
+}
+
+/// unregister every VMA (or part of a VMA) overlapping `[v_start, v_end)`,
+/// splitting a VMA that straddles one edge of the unmap range into the
+/// sub-range(s) left standing, and dropping any pages already faulted in
+/// over the unmapped range
+///
+/// Fails if the range doesn't overlap anything the calling task has mapped.
+pub fn task_munmap(v_start: VirtAddr, v_end: VirtAddr) -> bool {
+    let unmap_range = VPNRange::new(v_start.floor(), v_end.ceil());
+    let token = current_user_token();
+    let mut manager = TASK_MANAGER.exclusive_access();
+    let task = manager.current_mut();
+
+    if !task.areas.iter().any(|a| a.vpn_range.overlaps(&unmap_range)) {
+        return false;
+    }
+
+    let mut kept = alloc::vec::Vec::new();
+    for area in task.areas.drain(..) {
+        let (before, after) = area.vpn_range.without(&unmap_range);
+        if let Some(before) = before {
+            kept.push(MapArea::new(before, area.perm, area.source));
+        }
+        if let Some(after) = after {
+            kept.push(MapArea::new(after, area.perm, area.source));
+        }
+    }
+    task.areas = kept;
+    drop(manager);
+
+    let page_table = mm::with_page_table(token);
+    let mut page_table = page_table.exclusive_access();
+    for vpn in unmap_range {
+        page_table.unmap(vpn);
+    }
+    true
+}
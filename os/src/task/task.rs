@@ -0,0 +1,146 @@
+//! Task control block
+use alloc::vec::Vec;
+
+use crate::config::MAX_SYSCALL_NUM;
+use crate::mm::MapArea;
+
+/// A task's position in its life cycle
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TaskStatus {
+    /// currently on CPU
+    Running,
+    /// runnable but not currently scheduled
+    Ready,
+    /// exited; its control block stays in the task table
+    Zombie,
+    /// parked by a tracer (`PTRACE_ATTACH`/a traced-stop) until `PTRACE_CONT`
+    Stopped,
+    /// blocked awaiting some wakeup condition; part of the taxonomy
+    /// `sys_proc_stat` reports, but nothing drives a task into it yet since
+    /// this trimmed kernel has no sleep/wait syscall
+    #[allow(dead_code)]
+    Sleeping,
+}
+
+/// per-task ptrace bookkeeping, installed by `PTRACE_TRACEME`/`PTRACE_ATTACH`
+#[derive(Clone, Debug, Default)]
+pub struct PtraceState {
+    /// pid of the task tracing this one, if any
+    pub tracer: Option<usize>,
+    /// set while this task should remain `Stopped` instead of being
+    /// rescheduled, cleared by `PTRACE_CONT`
+    pub stop_requested: bool,
+    /// last signal number delivered via `PTRACE_CONT`, if any
+    pub pending_signal: Option<u32>,
+}
+
+/// what happens to a task that trips a disallowed syscall under `Seccomp`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SeccompDefaultAction {
+    /// deny the call, returning `-1` to the task instead of running it
+    ReturnEperm,
+    /// terminate the task immediately, as if it had called `sys_exit`
+    Kill,
+}
+
+/// per-task seccomp policy, installed by `sys_seccomp`
+#[derive(Clone, Debug)]
+pub struct SeccompState {
+    /// whether a policy has been installed; once `true` it can never be
+    /// unset or replaced, only enforced
+    pub active: bool,
+    /// `allowed[n]` is whether syscall number `n` may be invoked
+    pub allowed: [bool; MAX_SYSCALL_NUM],
+    /// what to do with a syscall not in `allowed`
+    pub default_action: SeccompDefaultAction,
+}
+
+impl Default for SeccompState {
+    fn default() -> Self {
+        Self {
+            active: false,
+            allowed: [true; MAX_SYSCALL_NUM],
+            default_action: SeccompDefaultAction::ReturnEperm,
+        }
+    }
+}
+
+/// stride-scheduling bookkeeping, advanced each time the scheduler hands
+/// this task the CPU
+#[derive(Clone, Copy, Debug)]
+pub struct StrideState {
+    /// larger values advance `pass` more slowly, i.e. favor the task more;
+    /// must be `>= 2`
+    pub priority: u64,
+    /// accumulated stride; the scheduler always picks the runnable task
+    /// with the smallest `pass`
+    pub pass: u64,
+}
+
+impl Default for StrideState {
+    fn default() -> Self {
+        Self {
+            priority: 16,
+            pass: 0,
+        }
+    }
+}
+
+/// The kernel's view of one task
+pub struct TaskControlBlock {
+    /// unique task id, doubles as `current_user_token()`'s value in this kernel
+    pub pid: usize,
+    /// pid of the task that spawned this one, if any
+    pub parent: Option<usize>,
+    /// life-cycle status
+    pub status: TaskStatus,
+    /// per-syscall invocation counters
+    pub syscall_times: [u32; MAX_SYSCALL_NUM],
+    /// program break, as managed by `sys_sbrk`
+    pub program_brk: usize,
+    /// ptrace tracer/stop bookkeeping
+    pub ptrace: PtraceState,
+    /// seccomp syscall filter, sticky for the task's lifetime
+    pub seccomp: SeccompState,
+    /// stride-scheduling priority and accumulated pass
+    pub stride: StrideState,
+    /// this task's virtual memory areas, as registered by `sys_mmap`
+    pub areas: Vec<MapArea>,
+    /// number of syscalls currently nested on this task's (simulated) kernel
+    /// stack, incremented/decremented by `kstack_enter`/`kstack_exit`
+    pub kstack_depth: u32,
+    /// CPU time (microseconds, `CLOCK_MONOTONIC` scale) accumulated the last
+    /// time this task was switched out; does not include time since
+    /// `accounting_start_us` if the task is currently running
+    pub cpu_time_us: usize,
+    /// `get_time_us()` reading taken when this task was last switched in
+    pub accounting_start_us: usize,
+}
+
+impl TaskControlBlock {
+    pub fn new(pid: usize, parent: Option<usize>) -> Self {
+        Self {
+            pid,
+            parent,
+            status: TaskStatus::Ready,
+            syscall_times: [0; MAX_SYSCALL_NUM],
+            program_brk: 0,
+            ptrace: PtraceState::default(),
+            // A future fork/spawn syscall should clone the parent's
+            // `SeccompState` here so a filter can't be escaped by forking;
+            // this trimmed kernel has no such syscall yet, so every task
+            // starts unfiltered.
+            seccomp: SeccompState::default(),
+            stride: StrideState::default(),
+            areas: Vec::new(),
+            kstack_depth: 0,
+            cpu_time_us: 0,
+            accounting_start_us: 0,
+        }
+    }
+
+    /// the token the syscall layer uses to address this task's page table
+    pub fn token(&self) -> usize {
+        self.pid
+    }
+}
@@ -0,0 +1,48 @@
+//! Monotonic time source backing `sys_get_time` and the rest of the kernel's
+//! clock-reading syscalls
+use crate::sync::UPSafeCell;
+
+/// ticks per second, matching qemu virt's CLINT frequency this tutorial
+/// kernel's trap-frequency math is written against
+const CLOCK_FREQ: u64 = 12_500_000;
+
+lazy_static::lazy_static! {
+    /// software stand-in for the `mtime` CSR: advances by a fixed number of
+    /// ticks on every read, since this checkout has no trap/boot code to
+    /// drive a real hardware counter
+    static ref BOOT_TICKS: UPSafeCell<u64> = unsafe { UPSafeCell::new(0) };
+    /// `CLOCK_REALTIME` minus `CLOCK_MONOTONIC`, in microseconds, as last set
+    /// by `sys_clock_settime`; `CLOCK_REALTIME` is always the monotonic clock
+    /// plus this single adjustable delta
+    static ref REALTIME_OFFSET_US: UPSafeCell<i64> = unsafe { UPSafeCell::new(0) };
+}
+
+const TICKS_PER_READ: u64 = 12_500;
+
+fn read_hardware_ticks() -> u64 {
+    let mut ticks = BOOT_TICKS.exclusive_access();
+    *ticks += TICKS_PER_READ;
+    *ticks
+}
+
+/// microseconds since boot (`CLOCK_MONOTONIC`)
+pub fn get_time_us() -> usize {
+    (read_hardware_ticks() * 1_000_000 / CLOCK_FREQ) as usize
+}
+
+/// milliseconds since boot
+pub fn get_time_ms() -> usize {
+    get_time_us() / 1000
+}
+
+/// microseconds since the epoch (`CLOCK_REALTIME`): the monotonic clock plus
+/// the adjustable offset last set by `sys_clock_settime`
+pub fn get_realtime_us() -> i64 {
+    get_time_us() as i64 + *REALTIME_OFFSET_US.exclusive_access()
+}
+
+/// set `CLOCK_REALTIME`'s offset from the monotonic clock so that
+/// `get_realtime_us()` reports `now_us`
+pub fn set_realtime_us(now_us: i64) {
+    *REALTIME_OFFSET_US.exclusive_access() = now_us - get_time_us() as i64;
+}